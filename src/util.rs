@@ -1,6 +1,7 @@
 use cgmath::Point3;
 use cgmath::Vector3;
 use cgmath::prelude::*;
+use cgmath::BaseFloat;
 use cgmath::Deg;
 use cgmath::Rad;
 
@@ -25,39 +26,108 @@ Dot     Angle
 Source: http://chortle.ccsu.edu/vectorlessons/vch09/vch09_6.html
  */
 
-pub fn norm(p1: Point3<f32>, p2: Point3<f32>, p3: Point3<f32>) -> Vector3<f32> {
+// Per-type tolerances for the geometry primitives below, so the thresholds scale with
+// the precision of S instead of being hardcoded f32 literals. `small_num` guards
+// degenerate denominators/near-zero checks; `point_eq` is the looser "are these two
+// points/vectors the same" threshold; `segment_eq` is the tight "is this point on this
+// segment" threshold used by is_point_on_segment, distinct from point_eq's coarser
+// vector-equality magnitude.
+pub trait Tolerance: BaseFloat {
+    fn small_num() -> Self;
+    fn point_eq() -> Self;
+    fn segment_eq() -> Self;
+}
+
+impl Tolerance for f32 {
+    fn small_num() -> f32 { 0.00000001 }
+    fn point_eq() -> f32 { 0.01 }
+    fn segment_eq() -> f32 { 0.00001 }
+}
+
+impl Tolerance for f64 {
+    fn small_num() -> f64 { 0.0000000000001 }
+    fn point_eq() -> f64 { 0.000001 }
+    fn segment_eq() -> f64 { 0.000000001 }
+}
+
+pub fn norm<S: Tolerance>(p1: Point3<S>, p2: Point3<S>, p3: Point3<S>) -> Vector3<S> {
     let side1 = p2 - p1;
     let side2 = p3 - p1;
     let perp = side1.cross(side2);
     perp.normalize()
 }
 
-pub fn almost_eq(v1: Vector3<f32>, v2: Vector3<f32>) -> bool {
-    (v1.x - v2.x).abs() <= 0.01 &&
-        (v1.y - v2.y).abs() <= 0.01 &&
-        (v1.z - v2.z).abs() <= 0.01
+pub fn almost_eq<S: Tolerance>(v1: Vector3<S>, v2: Vector3<S>) -> bool {
+    let tol = S::point_eq();
+    (v1.x - v2.x).abs() <= tol &&
+        (v1.y - v2.y).abs() <= tol &&
+        (v1.z - v2.z).abs() <= tol
 }
 
 // Modifed from Reza Nourai's C# version: PointInTriangle
 // https://blogs.msdn.microsoft.com/rezanour/2011/08/07/barycentric-coordinates-and-point-in-triangle-tests/
-pub fn point_in_triangle(a: Point3<f32>, b: Point3<f32>, c: Point3<f32>, p: Point3<f32>) -> bool {
-    let u = b - a;
-    let v = c - a;
-    let w = p - a;
-    let v_cross_w = v.cross(w);
-    let v_cross_u = v.cross(u);
-    if v_cross_w.dot(v_cross_u) < 0.0 {
-        return false;
+pub fn point_in_triangle<S: Tolerance>(a: Point3<S>, b: Point3<S>, c: Point3<S>, p: Point3<S>) -> bool {
+    match barycentric(a, b, c, p) {
+        Some((u, v, w)) => u >= -S::small_num() && v >= -S::small_num() && w >= -S::small_num(),
+        None => false,
     }
-    let u_cross_w = u.cross(w);
-    let u_cross_v = u.cross(v);
-    if u_cross_w.dot(u_cross_v) < 0.0 {
-        return false;
+}
+
+// Cached-dot-product barycentric coordinates, so callers can reuse (u, v, w) to
+// interpolate normals/UVs/weights across the face instead of only getting a bool back.
+pub fn barycentric<S: Tolerance>(a: Point3<S>, b: Point3<S>, c: Point3<S>, p: Point3<S>) -> Option<(S, S, S)> {
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denom = d00 * d11 - d01 * d01;
+    if denom.abs() < S::small_num() {
+        return None;
+    }
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    let u = S::one() - v - w;
+    Some((u, v, w))
+}
+
+#[cfg(test)]
+mod barycentric_tests {
+    use super::*;
+
+    fn tri() -> (Point3<f32>, Point3<f32>, Point3<f32>) {
+        (Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0))
+    }
+
+    #[test]
+    fn barycentric_of_a_vertex_is_a_basis_vector() {
+        let (a, b, c) = tri();
+        let (u, v, w) = barycentric(a, b, c, a).unwrap();
+        assert!(almost_eq(Vector3::new(u, v, w), Vector3::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn barycentric_coords_sum_to_one() {
+        let (a, b, c) = tri();
+        let (u, v, w) = barycentric(a, b, c, Point3::new(0.25, 0.25, 0.0)).unwrap();
+        assert!((u + v + w - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn barycentric_of_degenerate_triangle_is_none() {
+        let collinear = (Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0));
+        assert!(barycentric(collinear.0, collinear.1, collinear.2, Point3::new(0.5, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn point_in_triangle_accepts_interior_and_rejects_exterior() {
+        let (a, b, c) = tri();
+        assert!(point_in_triangle(a, b, c, Point3::new(0.25, 0.25, 0.0)));
+        assert!(!point_in_triangle(a, b, c, Point3::new(2.0, 2.0, 0.0)));
     }
-    let denom = u_cross_v.magnitude();
-    let r = v_cross_w.magnitude() / denom;
-    let t = u_cross_w.magnitude() / denom;
-    r + t <= 1.0
 }
 
 // Modified from Cyranose's answer
@@ -80,52 +150,199 @@ pub enum PointSide {
     Coincident
 }
 
-pub fn point_side_on_plane(pt: Point3<f32>, pt_on_plane: Point3<f32>, norm: Vector3<f32>) -> PointSide {
-    let line = pt - pt_on_plane;
-    let dot = line.dot(norm);
-    if dot > 0.0 {
-        PointSide::Front
-    } else if dot < 0.0 {
-        PointSide::Back
-    } else {
-        PointSide::Coincident
-    }
+pub fn point_side_on_plane<S: Tolerance>(pt: Point3<S>, pt_on_plane: Point3<S>, norm: Vector3<S>) -> PointSide {
+    Plane::new(pt_on_plane, norm).classify_point(pt)
 }
 
 #[derive(PartialEq)]
 #[derive(Debug)]
-pub enum SegmentPlaneIntersect {
+pub enum SegmentPlaneIntersect<S> {
     NoIntersection,
     Parallel,
     LiesIn,
-    Intersection(Point3<f32>),
+    Intersection(Point3<S>),
 }
 
+// Aliases for existing f32 call sites, so downstream code written before this module
+// went generic over S still compiles unchanged.
+pub type SegmentPlaneIntersectF32 = SegmentPlaneIntersect<f32>;
+
 pub const SMALL_NUM : f32 = 0.00000001;
 
 // Modfied from the C++ version intersect3D_SegmentPlane
 // http://geomalgorithms.com/a05-_intersect-1.html
-pub fn intersect_of_segment_and_plane(p0: Point3<f32>, p1: Point3<f32>, pt_on_plane: Point3<f32>, norm: Vector3<f32>) -> SegmentPlaneIntersect {
-    let u = p1 - p0;
-    let w = p0 - pt_on_plane;
-    let d = norm.dot(u);
-    let n = -norm.dot(w);
-    if d.abs() < SMALL_NUM {
-        if n == 0.0 {
-            return SegmentPlaneIntersect::LiesIn;
+pub fn intersect_of_segment_and_plane<S: Tolerance>(p0: Point3<S>, p1: Point3<S>, pt_on_plane: Point3<S>, norm: Vector3<S>) -> SegmentPlaneIntersect<S> {
+    Plane::new(pt_on_plane, norm).intersect_segment(p0, p1)
+}
+
+// Gives the line two non-parallel planes meet along, as a point on the line plus its
+// (unnormalized) direction. Building block for clipping faces against arbitrary planes,
+// complementing the segment/plane machinery above.
+pub fn intersect_plane_plane<S: Tolerance>(p1_on: Point3<S>, n1: Vector3<S>, p2_on: Point3<S>, n2: Vector3<S>) -> Option<(Point3<S>, Vector3<S>)> {
+    let dir = n1.cross(n2);
+    if dir.magnitude2() < S::small_num() {
+        return None;
+    }
+    let d1 = n1.dot(Vector3 {x: p1_on.x, y: p1_on.y, z: p1_on.z});
+    let d2 = n2.dot(Vector3 {x: p2_on.x, y: p2_on.y, z: p2_on.z});
+    let point = (n2.cross(dir) * d1 + dir.cross(n1) * d2) / dir.dot(dir);
+    Some((Point3 {x: point.x, y: point.y, z: point.z}, dir))
+}
+
+#[cfg(test)]
+mod intersect_plane_plane_tests {
+    use super::*;
+
+    #[test]
+    fn parallel_planes_have_no_intersection() {
+        let p1 = Point3::new(0.0f32, 0.0, 0.0);
+        let p2 = Point3::new(0.0f32, 0.0, 1.0);
+        let n = Vector3::new(0.0, 0.0, 1.0);
+        assert!(intersect_plane_plane(p1, n, p2, n).is_none());
+    }
+
+    #[test]
+    fn perpendicular_planes_through_the_origin_meet_at_the_origin() {
+        let p1 = Point3::new(0.0f32, 0.0, 0.0);
+        let p2 = Point3::new(0.0f32, 0.0, 0.0);
+        let (point, dir) = intersect_plane_plane(p1, Vector3::new(0.0, 0.0, 1.0), p2, Vector3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!(almost_eq(Vector3::new(point.x, point.y, point.z), Vector3::new(0.0, 0.0, 0.0)));
+        assert!(almost_eq(Vector3::new(dir.y, dir.z, 0.0), Vector3::new(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn offset_perpendicular_planes_meet_on_the_expected_line() {
+        // z=5 plane meets y=0 plane along the line {(x, 0, 5)}.
+        let (point, _) = intersect_plane_plane(
+            Point3::new(0.0f32, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0),
+            Point3::new(0.0f32, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        ).unwrap();
+        assert!(almost_eq(Vector3::new(0.0, point.y, point.z), Vector3::new(0.0, 0.0, 5.0)));
+    }
+}
+
+// A plane in Hessian normal form: for any point p on the plane, normal.dot(p) + d == 0.
+// Callers that used to pass a loose (point_on_plane, normal) pair around should build a
+// Plane once and reuse it, since signed_distance() is cheap and the normal is normalized
+// only at construction time.
+#[derive(Debug, Clone, Copy)]
+pub struct Plane<S> {
+    pub normal: Vector3<S>,
+    pub d: S,
+}
+
+// Alias for existing f32 call sites, so downstream code written before this module
+// went generic over S still compiles unchanged.
+pub type PlaneF32 = Plane<f32>;
+
+impl<S: Tolerance> Plane<S> {
+    pub fn new(pt_on_plane: Point3<S>, normal: Vector3<S>) -> Plane<S> {
+        let normal = normal.normalize();
+        let d = -normal.dot(Vector3 {x: pt_on_plane.x, y: pt_on_plane.y, z: pt_on_plane.z});
+        Plane { normal, d }
+    }
+
+    // Returns None for collinear/degenerate points, whose cross product is zero and
+    // would otherwise normalize to a NaN normal.
+    pub fn from_points(p0: Point3<S>, p1: Point3<S>, p2: Point3<S>) -> Option<Plane<S>> {
+        let normal = norm(p0, p1, p2);
+        if !is_valid_norm(normal) {
+            return None;
         }
-        return SegmentPlaneIntersect::Parallel;
+        Some(Plane::new(p0, normal))
     }
-    let s_i = n / d;
-    if s_i < 0.0 || s_i > 1.0 || s_i.is_nan() || s_i.is_infinite() {
-        return SegmentPlaneIntersect::NoIntersection;
+
+    pub fn signed_distance(&self, p: Point3<S>) -> S {
+        self.normal.dot(Vector3 {x: p.x, y: p.y, z: p.z}) + self.d
+    }
+
+    pub fn classify_point(&self, p: Point3<S>) -> PointSide {
+        let dist = self.signed_distance(p);
+        if dist > S::zero() {
+            PointSide::Front
+        } else if dist < S::zero() {
+            PointSide::Back
+        } else {
+            PointSide::Coincident
+        }
+    }
+
+    pub fn project_point(&self, p: Point3<S>) -> Point3<S> {
+        p - self.normal * self.signed_distance(p)
+    }
+
+    // Modfied from the C++ version intersect3D_SegmentPlane
+    // http://geomalgorithms.com/a05-_intersect-1.html
+    pub fn intersect_segment(&self, p0: Point3<S>, p1: Point3<S>) -> SegmentPlaneIntersect<S> {
+        let u = p1 - p0;
+        let d = self.normal.dot(u);
+        let n = -self.signed_distance(p0);
+        if d.abs() < S::small_num() {
+            if n == S::zero() {
+                return SegmentPlaneIntersect::LiesIn;
+            }
+            return SegmentPlaneIntersect::Parallel;
+        }
+        let s_i = n / d;
+        if s_i < S::zero() || s_i > S::one() || s_i.is_nan() || s_i.is_infinite() {
+            return SegmentPlaneIntersect::NoIntersection;
+        }
+        SegmentPlaneIntersect::Intersection(p0 + (u * s_i))
+    }
+}
+
+#[cfg(test)]
+mod plane_tests {
+    use super::*;
+
+    fn xy_plane() -> Plane<f32> {
+        Plane::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn classify_point_front_back_and_coincident() {
+        let plane = xy_plane();
+        assert_eq!(plane.classify_point(Point3::new(0.0, 0.0, 1.0)), PointSide::Front);
+        assert_eq!(plane.classify_point(Point3::new(0.0, 0.0, -1.0)), PointSide::Back);
+        assert_eq!(plane.classify_point(Point3::new(5.0, -3.0, 0.0)), PointSide::Coincident);
+    }
+
+    #[test]
+    fn project_point_drops_onto_the_plane() {
+        let plane = xy_plane();
+        let projected = plane.project_point(Point3::new(3.0, 4.0, 7.0));
+        assert!(almost_eq(Vector3::new(projected.x, projected.y, projected.z), Vector3::new(3.0, 4.0, 0.0)));
+    }
+
+    #[test]
+    fn intersect_segment_crossing_the_plane() {
+        let plane = xy_plane();
+        match plane.intersect_segment(Point3::new(0.0, 0.0, -2.0), Point3::new(0.0, 0.0, 2.0)) {
+            SegmentPlaneIntersect::Intersection(p) => assert!(almost_eq(Vector3::new(p.x, p.y, p.z), Vector3::new(0.0, 0.0, 0.0))),
+            other => panic!("expected Intersection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn intersect_segment_parallel_to_the_plane() {
+        let plane = xy_plane();
+        let result = plane.intersect_segment(Point3::new(0.0, 0.0, 1.0), Point3::new(1.0, 1.0, 1.0));
+        assert_eq!(result, SegmentPlaneIntersect::Parallel);
+    }
+
+    #[test]
+    fn from_points_rejects_collinear_input() {
+        let collinear = Plane::from_points(Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0));
+        assert!(collinear.is_none());
     }
-    SegmentPlaneIntersect::Intersection(p0 + (s_i * u))
 }
 
 // Modified from intersectRayWithSquare
 // https://stackoverflow.com/questions/21114796/3d-ray-quad-intersection-test-in-java
-pub fn is_segment_and_quad_intersect(p0: Point3<f32>, p1: Point3<f32>, quad: &Vec<Point3<f32>>) -> bool {
+pub fn is_segment_and_quad_intersect<S: Tolerance>(p0: Point3<S>, p1: Point3<S>, quad: &Vec<Point3<S>>) -> bool {
+    if !Aabb::from_points(quad).intersects_segment(p0, p1) {
+        return false;
+    }
     let r1 = p0;
     let r2 = p1;
     let s1 = quad[0];
@@ -136,7 +353,7 @@ pub fn is_segment_and_quad_intersect(p0: Point3<f32>, p1: Point3<f32>, quad: &Ve
     let n = ds21.cross(ds31);
     let dr = r1 - r2;
     let ndotdr = n.dot(dr);
-    if ndotdr.abs() < SMALL_NUM {
+    if ndotdr.abs() < S::small_num() {
         return false;
     }
     let t = -n.dot(r1 - s1) / ndotdr;
@@ -144,28 +361,551 @@ pub fn is_segment_and_quad_intersect(p0: Point3<f32>, p1: Point3<f32>, quad: &Ve
     let dms1 = m - s1;
     let u = dms1.dot(ds21);
     let v = dms1.dot(ds31);
-    u >= 0.0 && u <= ds21.dot(ds21) && v >= 0.0 && v <= ds31.dot(ds31)
+    u >= S::zero() && u <= ds21.dot(ds21) && v >= S::zero() && v <= ds31.dot(ds31)
 }
 
-pub fn is_two_quads_intersect(first_quad: &Vec<Point3<f32>>, second_quad: &Vec<Point3<f32>>) -> bool {
-    for i in 0..second_quad.len() {
-        if is_segment_and_quad_intersect(second_quad[i], second_quad[(i + 1) % second_quad.len()], first_quad) {
-            return true;
+// Modified from Tomas Moller's "A Fast Triangle-Triangle Intersection Test" (1997),
+// using the interval-overlap method rather than testing edges against the other face.
+pub fn triangle_triangle_intersect<S: Tolerance>(t1: [Point3<S>; 3], t2: [Point3<S>; 3]) -> bool {
+    let n2 = (t2[1] - t2[0]).cross(t2[2] - t2[0]);
+    let d2 = -n2.dot(Vector3 {x: t2[0].x, y: t2[0].y, z: t2[0].z});
+    let dv = [
+        n2.dot(Vector3 {x: t1[0].x, y: t1[0].y, z: t1[0].z}) + d2,
+        n2.dot(Vector3 {x: t1[1].x, y: t1[1].y, z: t1[1].z}) + d2,
+        n2.dot(Vector3 {x: t1[2].x, y: t1[2].y, z: t1[2].z}) + d2,
+    ];
+    if same_nonzero_sign(dv[0], dv[1], dv[2]) {
+        return false;
+    }
+
+    let n1 = (t1[1] - t1[0]).cross(t1[2] - t1[0]);
+    let d1 = -n1.dot(Vector3 {x: t1[0].x, y: t1[0].y, z: t1[0].z});
+    let du = [
+        n1.dot(Vector3 {x: t2[0].x, y: t2[0].y, z: t2[0].z}) + d1,
+        n1.dot(Vector3 {x: t2[1].x, y: t2[1].y, z: t2[1].z}) + d1,
+        n1.dot(Vector3 {x: t2[2].x, y: t2[2].y, z: t2[2].z}) + d1,
+    ];
+    if same_nonzero_sign(du[0], du[1], du[2]) {
+        return false;
+    }
+
+    let dir = n1.cross(n2);
+    if dir.magnitude2() < S::small_num() {
+        // The two planes are (nearly) coincident; fall back to a 2D test in the
+        // projection that drops the dominant axis of the shared normal.
+        return coplanar_triangle_triangle_intersect(t1, t2, n1);
+    }
+
+    let (t1_min, t1_max) = triangle_line_interval(t1, dv, dir);
+    let (t2_min, t2_max) = triangle_line_interval(t2, du, dir);
+    t1_min <= t2_max && t2_min <= t1_max
+}
+
+fn same_nonzero_sign<S: Tolerance>(a: S, b: S, c: S) -> bool {
+    (a > S::small_num() && b > S::small_num() && c > S::small_num()) ||
+        (a < -S::small_num() && b < -S::small_num() && c < -S::small_num())
+}
+
+// Projects a triangle's vertices onto the line of direction `dir` and, using the
+// signed distances to the other triangle's plane, finds the parametric interval the
+// triangle carves out of that line. A vertex within small_num() of the plane contributes
+// its own projection directly (the touching/shared-edge case); otherwise an edge whose
+// endpoints straddle the plane contributes its interpolated crossing point. This avoids
+// dividing by `dist[i] - dist[j]` when the two are equal (e.g. two vertices shared with
+// an adjacent, coplanar-at-that-edge face), which previously produced NaN.
+fn triangle_line_interval<S: Tolerance>(tri: [Point3<S>; 3], dist: [S; 3], dir: Vector3<S>) -> (S, S) {
+    let proj = [
+        dir.dot(Vector3 {x: tri[0].x, y: tri[0].y, z: tri[0].z}),
+        dir.dot(Vector3 {x: tri[1].x, y: tri[1].y, z: tri[1].z}),
+        dir.dot(Vector3 {x: tri[2].x, y: tri[2].y, z: tri[2].z}),
+    ];
+    let mut t_min = S::infinity();
+    let mut t_max = S::neg_infinity();
+    for i in 0..3 {
+        if dist[i].abs() <= S::small_num() {
+            t_min = t_min.min(proj[i]);
+            t_max = t_max.max(proj[i]);
         }
     }
-    for i in 0..first_quad.len() {
-        if is_segment_and_quad_intersect(first_quad[i], first_quad[(i + 1) % first_quad.len()], second_quad) {
-            return true;
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if dist[i].abs() > S::small_num() && dist[j].abs() > S::small_num() && (dist[i] > S::zero()) != (dist[j] > S::zero()) {
+            let t = proj[i] + (proj[j] - proj[i]) * dist[i] / (dist[i] - dist[j]);
+            t_min = t_min.min(t);
+            t_max = t_max.max(t);
+        }
+    }
+    (t_min, t_max)
+}
+
+fn dominant_axis<S: Tolerance>(n: Vector3<S>) -> usize {
+    if n.x.abs() >= n.y.abs() && n.x.abs() >= n.z.abs() {
+        0
+    } else if n.y.abs() >= n.z.abs() {
+        1
+    } else {
+        2
+    }
+}
+
+fn project_2d<S: Tolerance>(p: Point3<S>, drop_axis: usize) -> (S, S) {
+    match drop_axis {
+        0 => (p.y, p.z),
+        1 => (p.x, p.z),
+        _ => (p.x, p.y),
+    }
+}
+
+fn segments_intersect_2d<S: Tolerance>(a0: (S, S), a1: (S, S), b0: (S, S), b1: (S, S)) -> bool {
+    fn cross<S: Tolerance>(o: (S, S), a: (S, S), b: (S, S)) -> S {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    }
+    let d1 = cross(b0, b1, a0);
+    let d2 = cross(b0, b1, a1);
+    let d3 = cross(a0, a1, b0);
+    let d4 = cross(a0, a1, b1);
+    ((d1 > S::zero() && d2 < S::zero()) || (d1 < S::zero() && d2 > S::zero())) &&
+        ((d3 > S::zero() && d4 < S::zero()) || (d3 < S::zero() && d4 > S::zero()))
+}
+
+fn point_in_triangle_2d<S: Tolerance>(p: (S, S), a: (S, S), b: (S, S), c: (S, S)) -> bool {
+    fn side<S: Tolerance>(p1: (S, S), p2: (S, S), p3: (S, S)) -> S {
+        (p1.0 - p3.0) * (p2.1 - p3.1) - (p2.0 - p3.0) * (p1.1 - p3.1)
+    }
+    let d1 = side(p, a, b);
+    let d2 = side(p, b, c);
+    let d3 = side(p, c, a);
+    let has_neg = d1 < S::zero() || d2 < S::zero() || d3 < S::zero();
+    let has_pos = d1 > S::zero() || d2 > S::zero() || d3 > S::zero();
+    !(has_neg && has_pos)
+}
+
+fn coplanar_triangle_triangle_intersect<S: Tolerance>(t1: [Point3<S>; 3], t2: [Point3<S>; 3], normal: Vector3<S>) -> bool {
+    let axis = dominant_axis(normal);
+    let a = [project_2d(t1[0], axis), project_2d(t1[1], axis), project_2d(t1[2], axis)];
+    let b = [project_2d(t2[0], axis), project_2d(t2[1], axis), project_2d(t2[2], axis)];
+    for i in 0..3 {
+        for j in 0..3 {
+            if segments_intersect_2d(a[i], a[(i + 1) % 3], b[j], b[(j + 1) % 3]) {
+                return true;
+            }
+        }
+    }
+    point_in_triangle_2d(a[0], b[0], b[1], b[2]) || point_in_triangle_2d(b[0], a[0], a[1], a[2])
+}
+
+fn quad_to_triangles<S: Tolerance>(quad: &[Point3<S>]) -> [[Point3<S>; 3]; 2] {
+    [[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]]
+}
+
+pub fn is_two_quads_intersect<S: Tolerance>(first_quad: &[Point3<S>], second_quad: &[Point3<S>]) -> bool {
+    let first_tris = quad_to_triangles(first_quad);
+    let second_tris = quad_to_triangles(second_quad);
+    for t1 in first_tris.iter() {
+        for t2 in second_tris.iter() {
+            if triangle_triangle_intersect(*t1, *t2) {
+                return true;
+            }
         }
     }
     false
 }
 
-pub fn is_point_on_segment(point: Point3<f32>, seg_begin: Point3<f32>, seg_end: Point3<f32>) -> bool {
+#[cfg(test)]
+mod triangle_triangle_intersect_tests {
+    use super::*;
+
+    #[test]
+    fn disjoint_triangles_do_not_intersect() {
+        let t1 = [Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0)];
+        let t2 = [Point3::new(10.0f32, 10.0, 10.0), Point3::new(11.0, 10.0, 10.0), Point3::new(10.0, 11.0, 10.0)];
+        assert!(!triangle_triangle_intersect(t1, t2));
+    }
+
+    #[test]
+    fn piercing_triangles_intersect() {
+        let t1 = [Point3::new(-1.0f32, 0.0, -1.0), Point3::new(1.0, 0.0, -1.0), Point3::new(0.0, 0.0, 1.0)];
+        let t2 = [Point3::new(0.0f32, -1.0, 0.0), Point3::new(0.0, 1.0, -1.0), Point3::new(0.0, 1.0, 1.0)];
+        assert!(triangle_triangle_intersect(t1, t2));
+    }
+
+    // Two faces sharing a full edge is the single most common adjacency in a mesh; the
+    // shared vertices give both of that edge's signed distances to the other plane the
+    // exact same value, which used to divide-by-zero into NaN and read as "no intersection".
+    #[test]
+    fn triangles_sharing_an_edge_intersect() {
+        let t1 = [Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0)];
+        let t2 = [Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 1.0)];
+        assert!(triangle_triangle_intersect(t1, t2));
+    }
+
+    #[test]
+    fn f64_disjoint_triangles_do_not_intersect() {
+        let t1 = [Point3::new(0.0f64, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0)];
+        let t2 = [Point3::new(10.0f64, 10.0, 10.0), Point3::new(11.0, 10.0, 10.0), Point3::new(10.0, 11.0, 10.0)];
+        assert!(!triangle_triangle_intersect(t1, t2));
+    }
+}
+
+// Axis-aligned bounding box, used to cheaply reject pairs of quads before falling back
+// to the exact triangle-triangle test.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb<S> {
+    pub min: Point3<S>,
+    pub max: Point3<S>,
+}
+
+// Alias for existing f32 call sites, so downstream code written before this module
+// went generic over S still compiles unchanged.
+pub type AabbF32 = Aabb<f32>;
+
+impl<S: Tolerance> Aabb<S> {
+    pub fn from_points(points: &[Point3<S>]) -> Aabb<S> {
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in points.iter().skip(1) {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            min.z = min.z.min(p.z);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+            max.z = max.z.max(p.z);
+        }
+        Aabb { min, max }
+    }
+
+    pub fn center(&self) -> Point3<S> {
+        let two = S::one() + S::one();
+        Point3 {
+            x: (self.min.x + self.max.x) / two,
+            y: (self.min.y + self.max.y) / two,
+            z: (self.min.z + self.max.z) / two,
+        }
+    }
+
+    pub fn expand(&self, other: &Aabb<S>) -> Aabb<S> {
+        Aabb {
+            min: Point3 {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+                z: self.min.z.min(other.min.z),
+            },
+            max: Point3 {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+                z: self.max.z.max(other.max.z),
+            },
+        }
+    }
+
+    pub fn intersects(&self, other: &Aabb<S>) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x &&
+            self.min.y <= other.max.y && self.max.y >= other.min.y &&
+            self.min.z <= other.max.z && self.max.z >= other.min.z
+    }
+
+    // Per-axis slab test: compute the entry/exit parameter of the segment against the
+    // box's interval on that axis and reject as soon as the accumulated interval is empty.
+    pub fn intersects_segment(&self, p0: Point3<S>, p1: Point3<S>) -> bool {
+        let dir = p1 - p0;
+        let mut t_near = S::zero();
+        let mut t_far = S::one();
+        for axis in 0..3 {
+            let (o, d, lo, hi) = match axis {
+                0 => (p0.x, dir.x, self.min.x, self.max.x),
+                1 => (p0.y, dir.y, self.min.y, self.max.y),
+                _ => (p0.z, dir.z, self.min.z, self.max.z),
+            };
+            if d.abs() < S::small_num() {
+                if o < lo || o > hi {
+                    return false;
+                }
+                continue;
+            }
+            let mut t0 = (lo - o) / d;
+            let mut t1 = (hi - o) / d;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_near = t_near.max(t0);
+            t_far = t_far.min(t1);
+            if t_near > t_far {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// A small BVH over a set of quads' bounding boxes, used only to prune the O(quads^2)
+// pairwise overlap test down to the pairs whose boxes actually overlap.
+enum BvhNode<S> {
+    Leaf { bounds: Aabb<S>, quad_index: usize },
+    Branch { bounds: Aabb<S>, left: Box<BvhNode<S>>, right: Box<BvhNode<S>> },
+}
+
+impl<S: Tolerance> BvhNode<S> {
+    fn bounds(&self) -> Aabb<S> {
+        match self {
+            BvhNode::Leaf { bounds, .. } => *bounds,
+            BvhNode::Branch { bounds, .. } => *bounds,
+        }
+    }
+
+    fn build(mut items: Vec<(usize, Aabb<S>)>) -> BvhNode<S> {
+        if items.len() == 1 {
+            let (quad_index, bounds) = items[0];
+            return BvhNode::Leaf { bounds, quad_index };
+        }
+        let bounds = items.iter().skip(1).fold(items[0].1, |acc, (_, b)| acc.expand(b));
+        let extent = Vector3 {
+            x: bounds.max.x - bounds.min.x,
+            y: bounds.max.y - bounds.min.y,
+            z: bounds.max.z - bounds.min.z,
+        };
+        let axis = dominant_axis(extent);
+        items.sort_by(|a, b| {
+            let ca = a.1.center();
+            let cb = b.1.center();
+            let (va, vb) = match axis {
+                0 => (ca.x, cb.x),
+                1 => (ca.y, cb.y),
+                _ => (ca.z, cb.z),
+            };
+            va.partial_cmp(&vb).unwrap()
+        });
+        let mid = items.len() / 2;
+        let right_items = items.split_off(mid);
+        let left = BvhNode::build(items);
+        let right = BvhNode::build(right_items);
+        BvhNode::Branch { bounds, left: Box::new(left), right: Box::new(right) }
+    }
+
+    fn collect_overlapping_pairs(&self, other: &BvhNode<S>, pairs: &mut Vec<(usize, usize)>) {
+        if !self.bounds().intersects(&other.bounds()) {
+            return;
+        }
+        match (self, other) {
+            (BvhNode::Leaf { quad_index: i, .. }, BvhNode::Leaf { quad_index: j, .. }) => {
+                pairs.push(if i < j { (*i, *j) } else { (*j, *i) });
+            }
+            (BvhNode::Leaf { .. }, BvhNode::Branch { left, right, .. }) => {
+                self.collect_overlapping_pairs(left, pairs);
+                self.collect_overlapping_pairs(right, pairs);
+            }
+            (BvhNode::Branch { left, right, .. }, BvhNode::Leaf { .. }) => {
+                left.collect_overlapping_pairs(other, pairs);
+                right.collect_overlapping_pairs(other, pairs);
+            }
+            (BvhNode::Branch { left: l1, right: r1, .. }, BvhNode::Branch { left: l2, right: r2, .. }) => {
+                l1.collect_overlapping_pairs(l2, pairs);
+                l1.collect_overlapping_pairs(r2, pairs);
+                r1.collect_overlapping_pairs(l2, pairs);
+                r1.collect_overlapping_pairs(r2, pairs);
+            }
+        }
+    }
+
+    // Enumerates every distinct pair of leaves with overlapping boxes, visiting each
+    // unordered pair exactly once via the left/right split at their lowest common ancestor.
+    fn self_overlapping_pairs(&self, pairs: &mut Vec<(usize, usize)>) {
+        if let BvhNode::Branch { left, right, .. } = self {
+            left.self_overlapping_pairs(pairs);
+            right.self_overlapping_pairs(pairs);
+            left.collect_overlapping_pairs(right, pairs);
+        }
+    }
+}
+
+// AABB-pruned broad phase over a BVH, falling back to the exact is_two_quads_intersect
+// test only for the pairs whose bounding boxes actually overlap.
+pub fn overlapping_quad_pairs<S: Tolerance>(quads: &[Vec<Point3<S>>]) -> Vec<(usize, usize)> {
+    if quads.len() < 2 {
+        return Vec::new();
+    }
+    let items: Vec<(usize, Aabb<S>)> = quads.iter()
+        .enumerate()
+        .map(|(i, quad)| (i, Aabb::from_points(quad)))
+        .collect();
+    let bvh = BvhNode::build(items);
+    let mut candidates = Vec::new();
+    bvh.self_overlapping_pairs(&mut candidates);
+    candidates.into_iter()
+        .filter(|(i, j)| is_two_quads_intersect(&quads[*i], &quads[*j]))
+        .collect()
+}
+
+#[cfg(test)]
+mod aabb_and_overlap_tests {
+    use super::*;
+
+    #[test]
+    fn from_points_and_center() {
+        let points = [Point3::new(0.0f32, -1.0, 2.0), Point3::new(4.0, 3.0, -2.0)];
+        let aabb = Aabb::from_points(&points);
+        assert_eq!(aabb.min, Point3::new(0.0, -1.0, -2.0));
+        assert_eq!(aabb.max, Point3::new(4.0, 3.0, 2.0));
+        assert_eq!(aabb.center(), Point3::new(2.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn expand_grows_to_cover_both_boxes() {
+        let a = Aabb::from_points(&[Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)]);
+        let b = Aabb::from_points(&[Point3::new(-1.0f32, 2.0, 0.5), Point3::new(0.5, 3.0, 4.0)]);
+        let expanded = a.expand(&b);
+        assert_eq!(expanded.min, Point3::new(-1.0, 0.0, 0.0));
+        assert_eq!(expanded.max, Point3::new(1.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn intersects_overlapping_and_disjoint_boxes() {
+        let a = Aabb::from_points(&[Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)]);
+        let touching = Aabb::from_points(&[Point3::new(1.0f32, 0.0, 0.0), Point3::new(2.0, 1.0, 1.0)]);
+        let disjoint = Aabb::from_points(&[Point3::new(10.0f32, 10.0, 10.0), Point3::new(11.0, 11.0, 11.0)]);
+        assert!(a.intersects(&touching));
+        assert!(!a.intersects(&disjoint));
+    }
+
+    #[test]
+    fn intersects_segment_hits_and_misses() {
+        let aabb = Aabb::from_points(&[Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)]);
+        assert!(aabb.intersects_segment(Point3::new(0.5, 0.5, -5.0), Point3::new(0.5, 0.5, 5.0)));
+        assert!(!aabb.intersects_segment(Point3::new(5.0, 5.0, -5.0), Point3::new(5.0, 5.0, 5.0)));
+    }
+
+    // A zero-size "quad" (all four points coincident) still needs a well-formed,
+    // non-empty AABB so it neither vanishes from nor corrupts the BVH.
+    #[test]
+    fn from_points_handles_a_zero_size_box() {
+        let p = Point3::new(3.0f32, 3.0, 3.0);
+        let aabb = Aabb::from_points(&[p, p, p, p]);
+        assert_eq!(aabb.min, p);
+        assert_eq!(aabb.max, p);
+        assert!(aabb.intersects(&aabb));
+    }
+
+    #[test]
+    fn overlapping_quad_pairs_finds_overlap_and_skips_disjoint() {
+        let quads = vec![
+            vec![Point3::new(0.0f32, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0)],
+            vec![Point3::new(0.5f32, 0.5, -0.5), Point3::new(1.5, 0.5, -0.5), Point3::new(1.5, 1.5, 0.5), Point3::new(0.5, 1.5, 0.5)],
+            vec![Point3::new(10.0f32, 10.0, 10.0), Point3::new(11.0, 10.0, 10.0), Point3::new(11.0, 11.0, 10.0), Point3::new(10.0, 11.0, 10.0)],
+        ];
+        let pairs = overlapping_quad_pairs(&quads);
+        assert_eq!(pairs, vec![(0, 1)]);
+    }
+}
+
+// Ear-clipping triangulation of an arbitrary planar polygon, needed before exporting
+// n-gon faces to triangle-only formats or feeding them into triangle_triangle_intersect.
+// Bails out with whatever triangles it already clipped (possibly none) on collinear or
+// otherwise degenerate vertices rather than looping forever.
+pub fn triangulate_polygon<S: Tolerance>(points: &[Point3<S>], normal: Vector3<S>) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let axis = dominant_axis(normal);
+    let projected: Vec<(S, S)> = points.iter().map(|p| project_2d(*p, axis)).collect();
+    let signed_area: S = (0..projected.len()).map(|i| {
+        let (x0, y0) = projected[i];
+        let (x1, y1) = projected[(i + 1) % projected.len()];
+        x0 * y1 - x1 * y0
+    }).fold(S::zero(), |acc, v| acc + v);
+    if signed_area.abs() < S::small_num() {
+        return Vec::new();
+    }
+    let ccw = signed_area > S::zero();
+
+    let mut ring: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::new();
+    while ring.len() > 3 {
+        let n = ring.len();
+        let mut ear_found = false;
+        for i in 0..n {
+            let prev = ring[(i + n - 1) % n];
+            let cur = ring[i];
+            let next = ring[(i + 1) % n];
+            if !is_convex_vertex(projected[prev], projected[cur], projected[next], ccw) {
+                continue;
+            }
+            let ear_contains_other_vertex = ring.iter()
+                .filter(|&&v| v != prev && v != cur && v != next)
+                .any(|&v| point_in_triangle_2d(projected[v], projected[prev], projected[cur], projected[next]));
+            if ear_contains_other_vertex {
+                continue;
+            }
+            triangles.push([prev, cur, next]);
+            ring.remove(i);
+            ear_found = true;
+            break;
+        }
+        if !ear_found {
+            return triangles;
+        }
+    }
+    triangles.push([ring[0], ring[1], ring[2]]);
+    triangles
+}
+
+fn is_convex_vertex<S: Tolerance>(prev: (S, S), cur: (S, S), next: (S, S), ccw: bool) -> bool {
+    let cross = (cur.0 - prev.0) * (next.1 - prev.1) - (cur.1 - prev.1) * (next.0 - prev.0);
+    if ccw { cross > S::small_num() } else { cross < -S::small_num() }
+}
+
+#[cfg(test)]
+mod triangulate_polygon_tests {
+    use super::*;
+
+    #[test]
+    fn square_triangulates_into_two_triangles() {
+        let square = [
+            Point3::new(0.0f32, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let triangles = triangulate_polygon(&square, Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(triangles.len(), 2);
+        let mut covered: Vec<usize> = triangles.iter().flatten().cloned().collect();
+        covered.sort();
+        covered.dedup();
+        assert_eq!(covered, vec![0, 1, 2, 3]);
+    }
+
+    // An L-shaped hexagon has one reflex vertex, (1, 1), which lies on or inside some
+    // candidate ears, exercising the ear-rejection path rather than only the
+    // always-convex quad case above.
+    #[test]
+    fn concave_l_shape_triangulates_into_four_triangles() {
+        let l_shape = [
+            Point3::new(0.0f32, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(2.0, 1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(1.0, 2.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+        let triangles = triangulate_polygon(&l_shape, Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(triangles.len(), 4);
+    }
+
+    #[test]
+    fn collinear_points_triangulate_to_nothing() {
+        let collinear = [
+            Point3::new(0.0f32, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+        ];
+        let triangles = triangulate_polygon(&collinear, Vector3::new(0.0, 0.0, 1.0));
+        assert!(triangles.is_empty());
+    }
+}
+
+pub fn is_point_on_segment<S: Tolerance>(point: Point3<S>, seg_begin: Point3<S>, seg_end: Point3<S>) -> bool {
     let v = seg_end - seg_begin;
     let w = point - seg_begin;
     let w_dot_v = w.dot(v);
-    if w_dot_v <= 0.0 {
+    if w_dot_v <= S::zero() {
         return false;
     }
     let v_dot_v = v.dot(v);
@@ -174,10 +914,39 @@ pub fn is_point_on_segment(point: Point3<f32>, seg_begin: Point3<f32>, seg_end:
     }
     let t = seg_begin + (v * (w_dot_v / v_dot_v));
     let dist = t.distance(point);
-    dist <= 0.00001
+    dist <= S::segment_eq()
+}
+
+#[cfg(test)]
+mod is_point_on_segment_tests {
+    use super::*;
+
+    #[test]
+    fn point_on_segment_is_accepted() {
+        let begin = Point3::new(0.0f32, 0.0, 0.0);
+        let end = Point3::new(10.0f32, 0.0, 0.0);
+        assert!(is_point_on_segment(Point3::new(5.0, 0.0, 0.0), begin, end));
+    }
+
+    // Regression test for the segment_eq()-vs-point_eq() mixup: point_eq() (0.01 for
+    // f32) is 1000x looser than the ~1e-5 this function was tuned to, so a point this
+    // far off the segment must stay rejected.
+    #[test]
+    fn point_a_tenth_of_a_millimeter_off_segment_is_rejected() {
+        let begin = Point3::new(0.0f32, 0.0, 0.0);
+        let end = Point3::new(10.0f32, 0.0, 0.0);
+        assert!(!is_point_on_segment(Point3::new(5.0, 0.0001, 0.0), begin, end));
+    }
+
+    #[test]
+    fn point_beyond_segment_end_is_rejected() {
+        let begin = Point3::new(0.0f32, 0.0, 0.0);
+        let end = Point3::new(10.0f32, 0.0, 0.0);
+        assert!(!is_point_on_segment(Point3::new(11.0, 0.0, 0.0), begin, end));
+    }
 }
 
-pub fn is_valid_norm(norm: Vector3<f32>) -> bool {
+pub fn is_valid_norm<S: BaseFloat>(norm: Vector3<S>) -> bool {
     !norm.x.is_nan() && !norm.y.is_nan() && !norm.z.is_nan()
 }
 